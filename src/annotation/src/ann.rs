@@ -0,0 +1,153 @@
+//! Approximate nearest-neighbor index for embedding retrieval
+//!
+//! Wraps an rstar R-tree over L2-normalized embedding vectors so `GNNEmbedder::most_similar`
+//! and the embedding branch of `SimilarityFunction::similar` can answer top-k queries in
+//! sublinear time instead of scanning every card. Cosine similarity over L2-normalized
+//! vectors is rank-equivalent to Euclidean nearest neighbor, so vectors are normalized at
+//! both build and query time to make the R-tree's distance ordering match cosine ordering.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use rstar::{Point, PointDistance, RTree, RTreeObject, AABB};
+
+/// Maximum embedding dimensionality the R-tree can index; larger embeddings fall back to
+/// an exact scan. Trailing dimensions are zero-padded, which leaves distance ordering
+/// unaffected since the padding is identical across every indexed vector and the query.
+const MAX_INDEX_DIM: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct IndexPoint([f32; MAX_INDEX_DIM]);
+
+impl IndexPoint {
+    fn from_slice(v: &[f32]) -> Self {
+        let mut arr = [0f32; MAX_INDEX_DIM];
+        arr[..v.len()].copy_from_slice(v);
+        Self(arr)
+    }
+}
+
+impl Point for IndexPoint {
+    type Scalar = f32;
+    const DIMENSIONS: usize = MAX_INDEX_DIM;
+
+    fn generate(mut generator: impl FnMut(usize) -> f32) -> Self {
+        let mut arr = [0f32; MAX_INDEX_DIM];
+        for (i, slot) in arr.iter_mut().enumerate() {
+            *slot = generator(i);
+        }
+        Self(arr)
+    }
+
+    fn nth(&self, index: usize) -> f32 {
+        self.0[index]
+    }
+
+    fn nth_mut(&mut self, index: usize) -> &mut f32 {
+        &mut self.0[index]
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IndexedCard {
+    point: IndexPoint,
+    card: String,
+}
+
+impl RTreeObject for IndexedCard {
+    type Envelope = AABB<IndexPoint>;
+
+    fn envelope(&self) -> Self::Envelope {
+        AABB::from_point(self.point)
+    }
+}
+
+impl PointDistance for IndexedCard {
+    fn distance_2(&self, point: &IndexPoint) -> f32 {
+        squared_distance(&self.point, point)
+    }
+}
+
+fn squared_distance(a: &IndexPoint, b: &IndexPoint) -> f32 {
+    (0..MAX_INDEX_DIM)
+        .map(|i| {
+            let d = a.nth(i) - b.nth(i);
+            d * d
+        })
+        .sum()
+}
+
+/// L2-normalize a vector; the zero vector is left unchanged
+fn normalize_l2(v: &[f32]) -> Vec<f32> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        v.iter().map(|x| x / norm).collect()
+    } else {
+        v.to_vec()
+    }
+}
+
+/// Spatial index over L2-normalized embedding vectors for sublinear top-k cosine queries
+pub struct AnnIndex {
+    tree: RTree<IndexedCard>,
+}
+
+impl AnnIndex {
+    /// Build an index from an embeddings map; fails if any vector exceeds `MAX_INDEX_DIM`
+    pub fn build(embeddings: &HashMap<String, Vec<f32>>) -> Result<Self> {
+        let mut entries = Vec::with_capacity(embeddings.len());
+        for (card, emb) in embeddings {
+            if emb.len() > MAX_INDEX_DIM {
+                anyhow::bail!(
+                    "Cannot build ANN index: embedding dimension {} for '{}' exceeds the index's max dimension {}",
+                    emb.len(),
+                    card,
+                    MAX_INDEX_DIM
+                );
+            }
+            entries.push(IndexedCard {
+                point: IndexPoint::from_slice(&normalize_l2(emb)),
+                card: card.clone(),
+            });
+        }
+
+        Ok(Self {
+            tree: RTree::bulk_load(entries),
+        })
+    }
+
+    /// Query the `k` nearest neighbors of `vec` by cosine similarity, nearest first
+    pub fn query(&self, vec: &[f32], k: usize) -> Vec<(String, f32)> {
+        let point = IndexPoint::from_slice(&normalize_l2(vec));
+
+        self.tree
+            .nearest_neighbor_iter(&point)
+            .take(k)
+            .map(|entry| {
+                // For unit vectors, ||a - b||^2 = 2 - 2*cos(a, b)
+                let cosine = 1.0 - squared_distance(&entry.point, &point) / 2.0;
+                (entry.card.clone(), cosine)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ann_index_matches_exact_nearest_neighbor() {
+        let mut embeddings = HashMap::new();
+        embeddings.insert("Lightning Bolt".to_string(), vec![1.0, 0.0, 0.0]);
+        embeddings.insert("Chain Lightning".to_string(), vec![0.9, 0.1, 0.0]);
+        embeddings.insert("Brainstorm".to_string(), vec![0.0, 0.0, 1.0]);
+
+        let index = AnnIndex::build(&embeddings).unwrap();
+        let results = index.query(&[1.0, 0.0, 0.0], 3);
+
+        assert_eq!(results[0].0, "Lightning Bolt");
+        assert_eq!(results[1].0, "Chain Lightning");
+        assert_eq!(results[2].0, "Brainstorm");
+    }
+}
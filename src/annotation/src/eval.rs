@@ -5,6 +5,7 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::candidate::Candidate;
 use crate::test_set::TestSet;
 
 /// Evaluation metrics with confidence intervals
@@ -202,3 +203,165 @@ pub fn format_evaluation_report(metrics: &EvaluationMetrics) -> String {
     )
 }
 
+/// Ranking-quality metrics for a single query, computed from `Candidate::relevance` grades
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryRankingMetrics {
+    pub query: String,
+    pub dcg: f64,
+    pub ndcg: f64,
+    pub reciprocal_rank: f64,
+    pub recall: f64,
+}
+
+/// Ranking-quality report aggregated over a batch of queries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankingReport {
+    /// Mean NDCG@k across queries
+    pub mean_ndcg: f64,
+    /// Mean reciprocal rank across queries
+    pub mean_mrr: f64,
+    /// Mean Recall@k across queries
+    pub mean_recall: f64,
+    /// Per-query metric breakdown
+    pub per_query: Vec<QueryRankingMetrics>,
+}
+
+/// DCG@k = Σ_{i=1..k} rel_i / log2(i+1), over `relevances` in ranked order (1-indexed)
+pub fn dcg_at_k(relevances: &[u8], k: usize) -> f64 {
+    relevances
+        .iter()
+        .take(k)
+        .enumerate()
+        .map(|(i, &rel)| rel as f64 / ((i + 2) as f64).log2())
+        .sum()
+}
+
+/// NDCG@k = DCG@k / IDCG@k, where IDCG@k is the DCG of the ideally sorted relevances (0 if IDCG@k is 0)
+pub fn ndcg_at_k(relevances: &[u8], k: usize) -> f64 {
+    let dcg = dcg_at_k(relevances, k);
+
+    let mut ideal = relevances.to_vec();
+    ideal.sort_by(|a, b| b.cmp(a));
+    let idcg = dcg_at_k(&ideal, k);
+
+    if idcg > 0.0 {
+        dcg / idcg
+    } else {
+        0.0
+    }
+}
+
+/// Reciprocal rank of the first relevant (relevance > 0) result, 0 if none is relevant
+pub fn reciprocal_rank(relevances: &[u8]) -> f64 {
+    relevances
+        .iter()
+        .position(|&rel| rel > 0)
+        .map(|rank| 1.0 / (rank + 1) as f64)
+        .unwrap_or(0.0)
+}
+
+/// Recall@k = (relevant results in the top k) / (total relevant results), 0 if there are none
+pub fn recall_at_k(relevances: &[u8], k: usize) -> f64 {
+    let total_relevant = relevances.iter().filter(|&&rel| rel > 0).count();
+    if total_relevant == 0 {
+        return 0.0;
+    }
+
+    let relevant_in_top_k = relevances.iter().take(k).filter(|&&rel| rel > 0).count();
+    relevant_in_top_k as f64 / total_relevant as f64
+}
+
+/// Evaluate ranking quality across a batch of queries using each candidate's annotated
+/// `relevance` grade, in the order the candidates were ranked
+///
+/// This lets maintainers A/B the `rrf_config` and per-signal contributions against
+/// held-out annotations: swap in candidates from a different `FusionMode` or GNN variant
+/// and compare the resulting `RankingReport`.
+pub fn evaluate_ranking(queries: &[(String, Vec<Candidate>)], k: usize) -> RankingReport {
+    let per_query: Vec<QueryRankingMetrics> = queries
+        .iter()
+        .map(|(query, candidates)| {
+            let relevances: Vec<u8> = candidates
+                .iter()
+                .map(|c| c.relevance.unwrap_or(0))
+                .collect();
+
+            QueryRankingMetrics {
+                query: query.clone(),
+                dcg: dcg_at_k(&relevances, k),
+                ndcg: ndcg_at_k(&relevances, k),
+                reciprocal_rank: reciprocal_rank(&relevances),
+                recall: recall_at_k(&relevances, k),
+            }
+        })
+        .collect();
+
+    let n = per_query.len().max(1) as f64;
+    let mean_ndcg = per_query.iter().map(|m| m.ndcg).sum::<f64>() / n;
+    let mean_mrr = per_query.iter().map(|m| m.reciprocal_rank).sum::<f64>() / n;
+    let mean_recall = per_query.iter().map(|m| m.recall).sum::<f64>() / n;
+
+    RankingReport {
+        mean_ndcg,
+        mean_mrr,
+        mean_recall,
+        per_query,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_dcg_and_ndcg_at_k() {
+        // Best possible ordering: DCG should equal IDCG, so NDCG is 1.0
+        let relevances = vec![3, 2, 1, 0];
+        // DCG@4 = 3/log2(2) + 2/log2(3) + 1/log2(4) + 0/log2(5)
+        assert!((dcg_at_k(&relevances, 4) - (3.0 + 2.0 / 3f64.log2() + 0.5)).abs() < 1e-9);
+        assert!((ndcg_at_k(&relevances, 4) - 1.0).abs() < 1e-9);
+
+        // Worst ordering of the same grades: NDCG should be strictly less than 1.0
+        let worst = vec![0, 1, 2, 3];
+        assert!(ndcg_at_k(&worst, 4) < 1.0);
+    }
+
+    #[test]
+    fn test_reciprocal_rank() {
+        assert!((reciprocal_rank(&[0, 0, 1, 0]) - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(reciprocal_rank(&[0, 0, 0]), 0.0);
+    }
+
+    #[test]
+    fn test_recall_at_k() {
+        // 2 relevant total, 1 found in top 2
+        assert!((recall_at_k(&[1, 0, 1], 2) - 0.5).abs() < 1e-9);
+        assert_eq!(recall_at_k(&[0, 0, 0], 2), 0.0);
+    }
+
+    #[test]
+    fn test_evaluate_ranking_aggregates_per_query() {
+        let make = |rels: &[u8]| {
+            rels.iter()
+                .map(|&r| {
+                    let mut c = Candidate::new(format!("card{r}"), vec![], HashMap::new());
+                    c.relevance = Some(r);
+                    c
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let queries = vec![
+            ("query1".to_string(), make(&[3, 0, 1])),
+            ("query2".to_string(), make(&[0, 0, 0])),
+        ];
+
+        let report = evaluate_ranking(&queries, 3);
+        assert_eq!(report.per_query.len(), 2);
+        assert_eq!(report.per_query[1].ndcg, 0.0);
+        assert!(report.mean_ndcg > 0.0);
+    }
+}
+
@@ -7,9 +7,20 @@ use std::collections::HashMap;
 use std::path::Path;
 
 use anyhow::{Context, Result};
+use candle_core::{DType, Device, Tensor};
+use candle_nn::{Optimizer, VarBuilder, VarMap, SGD};
+use rand::Rng;
 use rank_refine::simd::cosine as cosine_sim;
 use serde::{Deserialize, Serialize};
 
+/// Negative samples drawn per positive edge for the link-prediction loss
+const NUM_NEGATIVE_SAMPLES: usize = 5;
+
+/// Largest edgelist (by distinct card count) `train` will build a dense adjacency for.
+/// `normalized_adjacency` allocates and matmuls an `n * n` f32 matrix every epoch, so this
+/// caps training at a few hundred MB and keeps epochs from going quadratic on full card pools.
+const MAX_TRAIN_NODES: usize = 4096;
+
 /// GNN model types supported
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum GNNModelType {
@@ -49,6 +60,11 @@ pub struct GNNEmbedder {
     embeddings: HashMap<String, Vec<f32>>,
     node_to_idx: HashMap<String, usize>,
     idx_to_node: HashMap<usize, String>,
+    /// Trained per-layer weight matrices (hidden_dim x hidden_dim, row-major), empty until trained
+    layer_weights: Vec<Vec<f32>>,
+    /// Spatial index over `embeddings` (see `build_index`); when present, `rank_by_embedding`
+    /// queries it in sublinear time instead of scanning every card
+    index: Option<crate::ann::AnnIndex>,
 }
 
 impl GNNEmbedder {
@@ -59,6 +75,8 @@ impl GNNEmbedder {
             embeddings: HashMap::new(),
             node_to_idx: HashMap::new(),
             idx_to_node: HashMap::new(),
+            layer_weights: Vec::new(),
+            index: None,
         }
     }
 
@@ -75,6 +93,8 @@ impl GNNEmbedder {
             node_to_idx: HashMap<String, usize>,
             idx_to_node: HashMap<usize, String>,
             embeddings: HashMap<String, Vec<f32>>,
+            #[serde(default)]
+            layer_weights: Vec<Vec<f32>>,
         }
 
         let state: GNNState = serde_json::from_str(&content)
@@ -98,25 +118,130 @@ impl GNNEmbedder {
             embeddings: state.embeddings,
             node_to_idx: state.node_to_idx,
             idx_to_node: state.idx_to_node,
+            layer_weights: state.layer_weights,
+            index: None,
         })
     }
 
-    /// Train GNN on edgelist (future: implement with candle/burn)
+    /// Train a GCN from an edgelist using candle, with an unsupervised link-prediction objective
     ///
-    /// For now, this is a placeholder. Options:
-    /// 1. Call out to Python PyG training script
-    /// 2. Implement with candle (lightweight, ONNX-compatible)
-    /// 3. Implement with burn (full-featured, PyTorch-like)
-    pub fn train(&mut self, _edgelist_path: &Path) -> Result<()> {
-        // TODO: Implement GNN training in Rust
-        // Option 1: Use candle for lightweight training
-        // Option 2: Use burn for full-featured training
-        // Option 3: Call Python script and load results
-        
-        anyhow::bail!(
-            "GNN training not yet implemented in Rust. \
-            Train with Python PyG and load with load_from_json()"
-        );
+    /// The edgelist is a two-column CSV of co-occurring card names (`card1,card2` per row),
+    /// matching the `pairs.csv` format used elsewhere in the pipeline. Builds the symmetric-
+    /// normalized adjacency Â = D̃^(-1/2)(A + I)D̃^(-1/2), runs `num_layers` GCN layers
+    /// (H^(l+1) = Â H^(l) W^(l), with ReLU between layers and none after the last) from
+    /// random node features, and fits the layer weights
+    /// by minimizing the negative-sampling link-prediction loss over the edgelist's edges.
+    /// On success, `embeddings`/`node_to_idx`/`idx_to_node` are populated directly, with no
+    /// Python round-trip required.
+    ///
+    /// `Â` is a dense `n * n` f32 matrix (see `normalized_adjacency`), so this scales
+    /// quadratically in the number of distinct cards in `edgelist_path` and rejects edgelists
+    /// with more than `MAX_TRAIN_NODES` distinct cards. Full Magic card pools (tens of
+    /// thousands of cards) exceed that bound; pre-filter the edgelist (e.g. to a format's legal
+    /// card pool, or to cards above a co-occurrence/degree threshold) before training on it.
+    pub fn train(&mut self, edgelist_path: &Path) -> Result<()> {
+        if !matches!(self.config.model_type, GNNModelType::GCN) {
+            anyhow::bail!(
+                "Native training is only implemented for GNNModelType::GCN, got {:?}. \
+                Train with Python PyG and load with load_from_json()",
+                self.config.model_type
+            );
+        }
+        if self.config.num_layers == 0 {
+            anyhow::bail!("GNNConfig::num_layers must be at least 1");
+        }
+
+        let edges_raw = read_edgelist(edgelist_path)?;
+        if edges_raw.is_empty() {
+            anyhow::bail!("Edgelist is empty: {}", edgelist_path.display());
+        }
+
+        let mut node_to_idx: HashMap<String, usize> = HashMap::new();
+        let mut idx_to_node: HashMap<usize, String> = HashMap::new();
+        for (a, b) in &edges_raw {
+            for card in [a, b] {
+                if !node_to_idx.contains_key(card) {
+                    let idx = node_to_idx.len();
+                    node_to_idx.insert(card.clone(), idx);
+                    idx_to_node.insert(idx, card.clone());
+                }
+            }
+        }
+        let n = node_to_idx.len();
+        if n > MAX_TRAIN_NODES {
+            anyhow::bail!(
+                "Edgelist has {n} distinct cards, exceeding MAX_TRAIN_NODES ({MAX_TRAIN_NODES}): \
+                normalized_adjacency builds a dense n*n f32 matrix, so training is quadratic in \
+                memory and time. Pre-filter the edgelist (e.g. to a format's legal card pool) \
+                before training on it: {}",
+                edgelist_path.display()
+            );
+        }
+        let hidden_dim = self.config.hidden_dim;
+
+        let device = Device::Cpu;
+        let edges: Vec<(usize, usize)> = edges_raw
+            .iter()
+            .map(|(a, b)| (node_to_idx[a], node_to_idx[b]))
+            .collect();
+
+        let a_hat = normalized_adjacency(n, &edges)?;
+        let a_hat = Tensor::from_vec(a_hat, (n, n), &device)?;
+        let h0 = Tensor::randn(0f32, 1f32, (n, hidden_dim), &device)?;
+
+        let varmap = VarMap::new();
+        let vb = VarBuilder::from_varmap(&varmap, DType::F32, &device);
+        let mut weights = Vec::with_capacity(self.config.num_layers);
+        for layer in 0..self.config.num_layers {
+            let w = vb.get_with_hints(
+                (hidden_dim, hidden_dim),
+                &format!("layer{layer}.weight"),
+                candle_nn::init::DEFAULT_KAIMING_NORMAL,
+            )?;
+            weights.push(w);
+        }
+
+        let mut optimizer = SGD::new(varmap.all_vars(), self.config.learning_rate as f64)?;
+        let mut rng = rand::thread_rng();
+
+        for _epoch in 0..self.config.epochs {
+            let mut h = h0.clone();
+            for (layer, w) in weights.iter().enumerate() {
+                h = a_hat.matmul(&h)?.matmul(w)?;
+                if layer + 1 < weights.len() {
+                    h = h.relu()?;
+                }
+            }
+
+            let loss = link_prediction_loss(&h, &edges, n, &mut rng, &device)?;
+            optimizer.backward_step(&loss)?;
+        }
+
+        // Final forward pass with the trained weights to produce the embeddings
+        let mut h = h0;
+        for (layer, w) in weights.iter().enumerate() {
+            h = a_hat.matmul(&h)?.matmul(w)?;
+            if layer + 1 < weights.len() {
+                h = h.relu()?;
+            }
+        }
+        let z = h.to_vec2::<f32>()?;
+
+        let mut embeddings = HashMap::with_capacity(n);
+        for (card, &idx) in &node_to_idx {
+            embeddings.insert(card.clone(), z[idx].clone());
+        }
+
+        self.layer_weights = weights
+            .iter()
+            .map(|w| Ok(w.flatten_all()?.to_vec1::<f32>()?))
+            .collect::<Result<Vec<_>>>()?;
+        self.embeddings = embeddings;
+        self.index = None; // stale now that `embeddings` changed; call `build_index` again to rebuild
+        self.node_to_idx = node_to_idx;
+        self.idx_to_node = idx_to_node;
+
+        Ok(())
     }
 
     /// Compute cosine similarity between two card embeddings
@@ -135,15 +260,46 @@ impl GNNEmbedder {
 
     /// Find most similar cards to a query
     pub fn most_similar(&self, query: &str, topn: usize) -> Vec<(String, f32)> {
-        let query_emb = match self.embeddings.get(query) {
-            Some(e) => e,
-            None => return Vec::new(),
-        };
+        match self.embeddings.get(query) {
+            Some(query_emb) => self.rank_by_embedding(query_emb, query, topn),
+            None => Vec::new(),
+        }
+    }
 
+    /// Like `most_similar`, but for a card with no stored embedding (e.g. a newly printed
+    /// card): infers its embedding inductively from `neighbors` via `embed_unseen` first
+    pub fn most_similar_unseen(&self, card: &str, neighbors: &[String], topn: usize) -> Vec<(String, f32)> {
+        match self.embed_unseen(card, neighbors) {
+            Some(emb) => self.rank_by_embedding(&emb, card, topn),
+            None => Vec::new(),
+        }
+    }
+
+    /// Rank every stored embedding (other than `exclude`) by cosine similarity to `query_emb`.
+    /// Uses `self.index` when present (see `build_index`) to answer in sublinear time instead
+    /// of scanning every card.
+    fn rank_by_embedding(&self, query_emb: &[f32], exclude: &str, topn: usize) -> Vec<(String, f32)> {
+        if let Some(index) = &self.index {
+            let mut similarities = index.query(query_emb, topn * 2 + 1); // +1 since `exclude` may come back
+            similarities.retain(|(card, _)| card != exclude);
+            similarities.truncate(topn);
+            return similarities;
+        }
+
+        #[cfg(feature = "parallel")]
+        let mut similarities: Vec<(String, f32)> = {
+            use rayon::prelude::*;
+            self.embeddings
+                .par_iter()
+                .filter(|(card, _)| card.as_str() != exclude)
+                .map(|(card, emb)| (card.clone(), cosine_sim(query_emb, emb)))
+                .collect()
+        };
+        #[cfg(not(feature = "parallel"))]
         let mut similarities: Vec<(String, f32)> = self
             .embeddings
             .iter()
-            .filter(|(card, _)| *card != query)
+            .filter(|(card, _)| card.as_str() != exclude)
             .map(|(card, emb)| (card.clone(), cosine_sim(query_emb, emb)))
             .collect();
 
@@ -152,11 +308,54 @@ impl GNNEmbedder {
         similarities
     }
 
+    /// Inductive inference for a card with no stored embedding, mirroring GraphSAGE's mean
+    /// aggregator: mean-pool the known (final-layer) embeddings of `neighbors`. The result
+    /// already lives in the same embedding space as every other stored embedding, so it's
+    /// returned as-is rather than re-entered into `layer_weights` — those matrices were
+    /// trained as part of `Â·H·W` forward steps over the full adjacency, and re-applying
+    /// them to an already-final vector with no adjacency term doesn't correspond to anything
+    /// `train` computes. Returns `None` if the card already has a stored embedding or none of
+    /// `neighbors` do.
+    pub fn embed_unseen(&self, card: &str, neighbors: &[String]) -> Option<Vec<f32>> {
+        if self.embeddings.contains_key(card) {
+            return None;
+        }
+
+        let neighbor_embeddings: Vec<&Vec<f32>> =
+            neighbors.iter().filter_map(|n| self.embeddings.get(n)).collect();
+        if neighbor_embeddings.is_empty() {
+            return None;
+        }
+
+        let dim = neighbor_embeddings[0].len();
+        let mut mean = vec![0f32; dim];
+        for emb in &neighbor_embeddings {
+            for (i, v) in emb.iter().enumerate() {
+                mean[i] += v;
+            }
+        }
+        let count = neighbor_embeddings.len() as f32;
+        for v in mean.iter_mut() {
+            *v /= count;
+        }
+
+        Some(mean)
+    }
+
     /// Get embedding for a card (returns None if not found)
     pub fn get_embedding(&self, card: &str) -> Option<&[f32]> {
         self.embeddings.get(card).map(|v| v.as_slice())
     }
 
+    /// Build a spatial index over `embeddings` and attach it to `self`, so `most_similar`,
+    /// `most_similar_unseen`, and `embed_unseen`-derived lookups all answer via `rank_by_embedding`
+    /// in sublinear time instead of scanning every card. Call again after `train` repopulates
+    /// `embeddings`, since the previous index is dropped (see the `self.index = None` in `train`).
+    pub fn build_index(&mut self) -> Result<()> {
+        self.index = Some(crate::ann::AnnIndex::build(&self.embeddings)?);
+        Ok(())
+    }
+
     /// Save embeddings to JSON (for sharing with Python or caching)
     pub fn save_to_json(&self, path: &Path) -> Result<()> {
         #[derive(Serialize)]
@@ -167,6 +366,7 @@ impl GNNEmbedder {
             node_to_idx: HashMap<String, usize>,
             idx_to_node: HashMap<usize, String>,
             embeddings: HashMap<String, Vec<f32>>,
+            layer_weights: Vec<Vec<f32>>,
         }
 
         let model_type_str = match self.config.model_type {
@@ -182,6 +382,7 @@ impl GNNEmbedder {
             node_to_idx: self.node_to_idx.clone(),
             idx_to_node: self.idx_to_node.clone(),
             embeddings: self.embeddings.clone(),
+            layer_weights: self.layer_weights.clone(),
         };
 
         let json = serde_json::to_string_pretty(&state)
@@ -196,6 +397,100 @@ impl GNNEmbedder {
 
 // Using rank_refine::simd::cosine for SIMD-accelerated similarity computation
 
+/// Read an edgelist CSV (`card1,card2` per row) into a list of co-occurring card pairs
+fn read_edgelist(path: &Path) -> Result<Vec<(String, String)>> {
+    let mut reader = csv::Reader::from_path(path)
+        .with_context(|| format!("Failed to open edgelist: {}", path.display()))?;
+
+    let mut edges = Vec::new();
+    for result in reader.records() {
+        let record = result.context("Failed to read edgelist record")?;
+        if record.len() < 2 {
+            continue;
+        }
+        let a = record.get(0).unwrap_or("").to_string();
+        let b = record.get(1).unwrap_or("").to_string();
+        if a.is_empty() || b.is_empty() {
+            continue;
+        }
+        edges.push((a, b));
+    }
+
+    Ok(edges)
+}
+
+/// Build the symmetric-normalized adjacency Â = D̃^(-1/2)(A + I)D̃^(-1/2) as a flat, row-major
+/// `n * n` buffer, where A is the (undirected) co-occurrence adjacency from `edges`.
+///
+/// Dense and `O(n^2)` in both memory and the per-epoch matmul in `train`; `train` enforces
+/// `MAX_TRAIN_NODES` so this never gets called with an `n` large enough to matter, but keep
+/// that in mind if this is ever called directly with a pre-filtered edgelist's node count.
+fn normalized_adjacency(n: usize, edges: &[(usize, usize)]) -> Result<Vec<f32>> {
+    let mut a = vec![0f32; n * n];
+    for i in 0..n {
+        a[i * n + i] = 1.0; // self loop
+    }
+    for &(u, v) in edges {
+        a[u * n + v] = 1.0;
+        a[v * n + u] = 1.0;
+    }
+
+    let degree: Vec<f32> = (0..n).map(|i| a[i * n..i * n + n].iter().sum()).collect();
+    let dinv_sqrt: Vec<f32> = degree.iter().map(|&d| if d > 0.0 { 1.0 / d.sqrt() } else { 0.0 }).collect();
+
+    for i in 0..n {
+        for j in 0..n {
+            a[i * n + j] *= dinv_sqrt[i] * dinv_sqrt[j];
+        }
+    }
+
+    Ok(a)
+}
+
+/// Unsupervised link-prediction loss: for each positive edge (u, v), minimize
+/// `-log σ(z_u·z_v) - Σ_w log σ(-z_u·z_w)` over `NUM_NEGATIVE_SAMPLES` negative nodes `w`
+fn link_prediction_loss(
+    z: &Tensor,
+    edges: &[(usize, usize)],
+    n: usize,
+    rng: &mut impl Rng,
+    device: &Device,
+) -> Result<Tensor> {
+    let num_edges = edges.len();
+    let u_idx: Vec<u32> = edges.iter().map(|&(u, _)| u as u32).collect();
+    let v_idx: Vec<u32> = edges.iter().map(|&(_, v)| v as u32).collect();
+
+    let mut neg_u_idx: Vec<u32> = Vec::with_capacity(num_edges * NUM_NEGATIVE_SAMPLES);
+    let mut neg_w_idx: Vec<u32> = Vec::with_capacity(num_edges * NUM_NEGATIVE_SAMPLES);
+    for &(u, _) in edges {
+        for _ in 0..NUM_NEGATIVE_SAMPLES {
+            neg_u_idx.push(u as u32);
+            neg_w_idx.push(rng.gen_range(0..n) as u32);
+        }
+    }
+
+    let u_idx = Tensor::from_vec(u_idx, num_edges, device)?;
+    let v_idx = Tensor::from_vec(v_idx, num_edges, device)?;
+    let neg_u_idx = Tensor::from_vec(neg_u_idx, num_edges * NUM_NEGATIVE_SAMPLES, device)?;
+    let neg_w_idx = Tensor::from_vec(neg_w_idx, num_edges * NUM_NEGATIVE_SAMPLES, device)?;
+
+    let pos_u = z.index_select(&u_idx, 0)?;
+    let pos_v = z.index_select(&v_idx, 0)?;
+    let pos_scores = (pos_u * pos_v)?.sum(1)?;
+    // -log sigmoid(x) == softplus(-x) == log(1 + exp(-x))
+    let pos_loss = pos_scores.neg()?.exp()?.affine(1.0, 1.0)?.log()?;
+
+    let neg_u = z.index_select(&neg_u_idx, 0)?;
+    let neg_w = z.index_select(&neg_w_idx, 0)?;
+    let neg_scores = (neg_u * neg_w)?.sum(1)?.reshape((num_edges, NUM_NEGATIVE_SAMPLES))?;
+    // -log sigmoid(-x) == softplus(x) == log(1 + exp(x))
+    let neg_loss = neg_scores.exp()?.affine(1.0, 1.0)?.log()?.sum(1)?;
+
+    let per_edge_loss = (pos_loss + neg_loss)?;
+    let loss = per_edge_loss.mean_all()?;
+    Ok(loss)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,6 +519,8 @@ mod tests {
             embeddings,
             node_to_idx: HashMap::new(),
             idx_to_node: HashMap::new(),
+            layer_weights: Vec::new(),
+            index: None,
         };
 
         let sim = embedder.similarity("Lightning Bolt", "Chain Lightning");
@@ -232,5 +529,110 @@ mod tests {
         let sim = embedder.similarity("Lightning Bolt", "Brainstorm");
         assert!(sim < 0.5); // Should be low similarity
     }
+
+    #[test]
+    fn test_normalized_adjacency_self_loops_and_symmetry() {
+        // Path graph 0-1-2: node 1 connects to both others, nodes 0 and 2 are not connected
+        let edges = vec![(0, 1), (1, 2)];
+        let a_hat = normalized_adjacency(3, &edges).unwrap();
+
+        // Symmetric
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!((a_hat[i * 3 + j] - a_hat[j * 3 + i]).abs() < 1e-6);
+            }
+        }
+
+        // Self loops give every node a nonzero diagonal entry
+        for i in 0..3 {
+            assert!(a_hat[i * 3 + i] > 0.0);
+        }
+
+        // Node 1 (degree 2 + self loop) connects to both 0 and 2
+        assert!(a_hat[1 * 3 + 0] > 0.0);
+        assert!(a_hat[1 * 3 + 2] > 0.0);
+    }
+
+    #[test]
+    fn test_embed_unseen_mean_pools_neighbors() {
+        let mut embeddings = HashMap::new();
+        embeddings.insert("Lightning Bolt".to_string(), vec![1.0, 0.0, 0.0]);
+        embeddings.insert("Chain Lightning".to_string(), vec![0.0, 1.0, 0.0]);
+
+        let embedder = GNNEmbedder {
+            config: GNNConfig::default(),
+            embeddings,
+            node_to_idx: HashMap::new(),
+            idx_to_node: HashMap::new(),
+            layer_weights: Vec::new(),
+            index: None,
+        };
+
+        // The mean-pooled neighbor embedding is returned as-is
+        let neighbors = vec!["Lightning Bolt".to_string(), "Chain Lightning".to_string()];
+        let unseen = embedder.embed_unseen("New Card", &neighbors).unwrap();
+        assert!((unseen[0] - 0.5).abs() < 1e-6);
+        assert!((unseen[1] - 0.5).abs() < 1e-6);
+
+        // A card that already has a stored embedding is not inferred
+        assert!(embedder.embed_unseen("Lightning Bolt", &neighbors).is_none());
+
+        // No known neighbors means no signal to infer from
+        assert!(embedder.embed_unseen("New Card", &["Unknown".to_string()]).is_none());
+    }
+
+    #[test]
+    fn test_train_populates_embeddings_and_similarity_works() {
+        let path = std::env::temp_dir().join(format!("gnn_train_test_{}.csv", std::process::id()));
+        {
+            let mut writer = csv::Writer::from_path(&path).unwrap();
+            writer.write_record(["card1", "card2"]).unwrap();
+            for (a, b) in [
+                ("Lightning Bolt", "Chain Lightning"),
+                ("Chain Lightning", "Lava Spike"),
+                ("Lava Spike", "Lightning Bolt"),
+                ("Brainstorm", "Ponder"),
+            ] {
+                writer.write_record([a, b]).unwrap();
+            }
+            writer.flush().unwrap();
+        }
+
+        let config = GNNConfig {
+            model_type: GNNModelType::GCN,
+            hidden_dim: 8,
+            num_layers: 2,
+            learning_rate: 0.05,
+            epochs: 20,
+        };
+        let mut embedder = GNNEmbedder::new(config);
+        let result = embedder.train(&path);
+        std::fs::remove_file(&path).ok();
+        result.unwrap();
+
+        let expected_cards = [
+            "Lightning Bolt",
+            "Chain Lightning",
+            "Lava Spike",
+            "Brainstorm",
+            "Ponder",
+        ];
+        assert_eq!(embedder.embeddings.len(), expected_cards.len());
+        assert_eq!(embedder.node_to_idx.len(), expected_cards.len());
+        assert_eq!(embedder.idx_to_node.len(), expected_cards.len());
+        for card in expected_cards {
+            let emb = embedder.get_embedding(card).unwrap();
+            assert_eq!(emb.len(), 8);
+        }
+        assert_eq!(embedder.layer_weights.len(), 2);
+
+        // similarity/most_similar should work against the freshly trained embeddings
+        let sim = embedder.similarity("Lightning Bolt", "Chain Lightning");
+        assert!(sim.is_finite());
+
+        let similar = embedder.most_similar("Lightning Bolt", 3);
+        assert!(!similar.is_empty());
+        assert!(similar.iter().all(|(card, _)| card != "Lightning Bolt"));
+    }
 }
 
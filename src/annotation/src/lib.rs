@@ -10,6 +10,7 @@ use anyhow::{Context, Result};
 use rank_fusion::RrfConfig;
 use serde::{Deserialize, Serialize};
 
+pub mod ann;
 pub mod candidate;
 pub mod eval;
 pub mod gnn;
@@ -18,6 +19,7 @@ pub mod signals;
 pub mod similarity;
 pub mod test_set;
 
+pub use ann::*;
 pub use candidate::*;
 pub use eval::*;
 pub use query::*;
@@ -104,6 +106,122 @@ pub fn generate_candidates_fused(
     Ok(result)
 }
 
+/// Min-max normalize a source's raw scores to [0, 1]; a constant source (or a single
+/// candidate) normalizes to 1.0 since there is no spread to scale against
+fn min_max_normalize(scores: &[(String, f32)]) -> HashMap<String, f32> {
+    if scores.is_empty() {
+        return HashMap::new();
+    }
+
+    let min = scores.iter().map(|(_, s)| *s).fold(f32::INFINITY, f32::min);
+    let max = scores.iter().map(|(_, s)| *s).fold(f32::NEG_INFINITY, f32::max);
+    let range = max - min;
+
+    scores
+        .iter()
+        .map(|(card, score)| {
+            let norm = if range > 0.0 { (score - min) / range } else { 1.0 };
+            (card.clone(), norm)
+        })
+        .collect()
+}
+
+/// Build candidates with source attribution the same way `generate_candidates_fused` does,
+/// but order them only by a caller-supplied per-card score (highest first)
+fn build_candidates_ranked_by(
+    sources: &[(&str, Vec<(String, f32)>)],
+    final_scores: &HashMap<String, f32>,
+) -> Vec<Candidate> {
+    let mut candidates: HashMap<String, Candidate> = HashMap::new();
+
+    for (source_name, source_list) in sources.iter() {
+        for (card, score) in source_list.iter() {
+            let candidate = candidates
+                .entry(card.clone())
+                .or_insert_with(|| Candidate::new(card.clone(), vec![], HashMap::new()));
+
+            candidate.sources.push(source_name.to_string());
+            candidate.scores.insert(source_name.to_string(), *score);
+        }
+    }
+
+    let mut result: Vec<Candidate> = candidates.into_values().collect();
+    result.sort_by(|a, b| {
+        let a_score = final_scores.get(&a.card).copied().unwrap_or(0.0);
+        let b_score = final_scores.get(&b.card).copied().unwrap_or(0.0);
+        b_score.partial_cmp(&a_score).unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    result
+}
+
+/// Fuse candidate sources via min-max normalization and a weighted convex combination
+/// (`FusionMode::Weighted`), rather than reciprocal-rank fusion
+pub fn generate_candidates_weighted(
+    _query: &str,
+    sources: &[(&str, Vec<(String, f32)>)],
+    weights: &HashMap<String, f32>,
+) -> Result<Vec<Candidate>> {
+    if sources.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut final_scores: HashMap<String, f32> = HashMap::new();
+    for (source_name, source_list) in sources.iter() {
+        let weight = weights.get(*source_name).copied().unwrap_or(1.0);
+        for (card, norm_score) in min_max_normalize(source_list) {
+            *final_scores.entry(card).or_insert(0.0) += weight * norm_score;
+        }
+    }
+
+    Ok(build_candidates_ranked_by(sources, &final_scores))
+}
+
+/// Fuse candidate sources via the `semantic_ratio` shortcut: blend the semantic sources
+/// (embedding, gnn) against the lexical sources (jaccard, sideboard, temporal) as
+/// `ratio * semantic + (1 - ratio) * lexical`, after min-max normalizing each source
+pub fn fuse_semantic_ratio(
+    _query: &str,
+    sources: &[(&str, Vec<(String, f32)>)],
+    ratio: f32,
+) -> Result<Vec<Candidate>> {
+    if sources.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let group_score = |names: &[&str]| -> HashMap<String, (f32, usize)> {
+        let mut totals: HashMap<String, (f32, usize)> = HashMap::new();
+        for (source_name, source_list) in sources.iter() {
+            if !names.contains(source_name) {
+                continue;
+            }
+            for (card, norm_score) in min_max_normalize(source_list) {
+                let entry = totals.entry(card).or_insert((0.0, 0));
+                entry.0 += norm_score;
+                entry.1 += 1;
+            }
+        }
+        totals
+    };
+
+    let semantic = group_score(similarity::SEMANTIC_SOURCES);
+    let lexical = group_score(similarity::LEXICAL_SOURCES);
+
+    let mut cards: HashMap<String, f32> = HashMap::new();
+    for card in semantic.keys().chain(lexical.keys()) {
+        cards.entry(card.clone()).or_insert(0.0);
+    }
+
+    let mut final_scores: HashMap<String, f32> = HashMap::new();
+    for card in cards.keys() {
+        let semantic_score = semantic.get(card).map(|(sum, n)| sum / *n as f32).unwrap_or(0.0);
+        let lexical_score = lexical.get(card).map(|(sum, n)| sum / *n as f32).unwrap_or(0.0);
+        final_scores.insert(card.clone(), ratio * semantic_score + (1.0 - ratio) * lexical_score);
+    }
+
+    Ok(build_candidates_ranked_by(sources, &final_scores))
+}
+
 /// Refine candidates using rank-refine (reranking with embeddings)
 pub fn refine_candidates(
     query_embedding: &[f32],
@@ -232,3 +350,34 @@ pub struct Instructions {
     #[serde(rename = "grading_guidelines")]
     pub grading_guidelines: Vec<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_candidates_weighted_favors_configured_source() {
+        let sources: Vec<(&str, Vec<(String, f32)>)> = vec![
+            ("embedding", vec![("A".to_string(), 1.0), ("B".to_string(), 0.0)]),
+            ("jaccard", vec![("A".to_string(), 0.0), ("B".to_string(), 1.0)]),
+        ];
+        let weights = HashMap::from([("embedding".to_string(), 2.0), ("jaccard".to_string(), 0.5)]);
+
+        let candidates = generate_candidates_weighted("query", &sources, &weights).unwrap();
+        assert_eq!(candidates[0].card, "A");
+    }
+
+    #[test]
+    fn test_fuse_semantic_ratio_pure_semantic_prefers_embedding_winner() {
+        let sources: Vec<(&str, Vec<(String, f32)>)> = vec![
+            ("embedding", vec![("A".to_string(), 1.0), ("B".to_string(), 0.0)]),
+            ("jaccard", vec![("A".to_string(), 0.0), ("B".to_string(), 1.0)]),
+        ];
+
+        let candidates = fuse_semantic_ratio("query", &sources, 1.0).unwrap();
+        assert_eq!(candidates[0].card, "A");
+
+        let candidates = fuse_semantic_ratio("query", &sources, 0.0).unwrap();
+        assert_eq!(candidates[0].card, "B");
+    }
+}
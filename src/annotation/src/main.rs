@@ -150,8 +150,7 @@ fn main() -> Result<()> {
             };
 
             let candidate_generator = move |query: &str| -> Result<Vec<Candidate>> {
-                use decksage_annotation::SimilarityFunction;
-                use rank_fusion::RrfConfig;
+                use decksage_annotation::{FusionMode, SimilarityFunction};
 
                 // Build similarity function with available signals
                 let sim_fn = SimilarityFunction {
@@ -160,7 +159,9 @@ fn main() -> Result<()> {
                     sideboard: sideboard_signal.clone(),
                     temporal: temporal_signal.clone(),
                     gnn: None, // TODO: Load GNN embeddings from JSON
-                    rrf_config: RrfConfig::default(),
+                    embedding_index: None,
+                    fusion_mode: FusionMode::default(),
+                    semantic_ratio: None,
                 };
 
                 sim_fn.similar(query, 20)
@@ -13,10 +13,36 @@ use anyhow::Result;
 use rank_fusion::RrfConfig;
 use rank_refine::simd::cosine as cosine_sim;
 
+use crate::ann::AnnIndex;
 use crate::candidate::Candidate;
 use crate::gnn::GNNEmbedder;
 use crate::signals::{SideboardSignal, TemporalSignal};
 
+/// Semantic sources blended against lexical sources by `SimilarityFunction::semantic_ratio`
+pub(crate) const SEMANTIC_SOURCES: &[&str] = &["embedding", "gnn"];
+pub(crate) const LEXICAL_SOURCES: &[&str] = &["jaccard", "sideboard", "temporal"];
+
+/// Fixed signal order `sources` is sorted into after the `parallel`-feature fan-out, so
+/// candidate/source attribution is deterministic regardless of thread scheduling
+const SIGNAL_ORDER: &[&str] = &["embedding", "jaccard", "sideboard", "temporal", "gnn"];
+
+/// How per-source similarity scores are fused into a final ranking
+#[derive(Debug, Clone)]
+pub enum FusionMode {
+    /// Reciprocal-rank fusion over all signals with equal footing
+    Rrf(RrfConfig),
+    /// Min-max normalize each source to [0, 1] and combine via a convex combination of
+    /// per-source weights (e.g. "embedding", "jaccard", "sideboard", "temporal", "gnn").
+    /// Sources with no configured weight default to a weight of 1.0.
+    Weighted { weights: HashMap<String, f32> },
+}
+
+impl Default for FusionMode {
+    fn default() -> Self {
+        FusionMode::Rrf(RrfConfig::default())
+    }
+}
+
 /// Similarity function that combines multiple signals
 pub struct SimilarityFunction {
     /// Embedding similarity (card -> embedding vector)
@@ -29,110 +55,223 @@ pub struct SimilarityFunction {
     pub temporal: Option<TemporalSignal>,
     /// GNN embeddings (learned graph representations)
     pub gnn: Option<GNNEmbedder>,
-    /// RRF configuration
-    pub rrf_config: RrfConfig,
+    /// Optional spatial index over `embeddings` (see `AnnIndex::build`); when present, the
+    /// embedding branch below queries it in sublinear time instead of scanning every card
+    pub embedding_index: Option<AnnIndex>,
+    /// How signals are fused into a final ranking (ignored if `semantic_ratio` is set)
+    pub fusion_mode: FusionMode,
+    /// Shortcut that blends the semantic sources (embedding, gnn) against the lexical
+    /// sources (jaccard, sideboard, temporal) as `ratio * semantic + (1 - ratio) * lexical`,
+    /// overriding `fusion_mode` when set. Mirrors hybrid-search tuning of dense-vector
+    /// relevance against exact co-occurrence evidence.
+    pub semantic_ratio: Option<f32>,
 }
 
 impl SimilarityFunction {
     /// Find similar cards to query
+    ///
+    /// Under the `parallel` feature, the independent signal sources (embedding, jaccard,
+    /// sideboard, temporal, gnn) are computed concurrently via `rayon::scope`, since each
+    /// produces its own ranked list before fusion.
     pub fn similar(&self, query: &str, k: usize) -> Result<Vec<Candidate>> {
-        let mut sources: Vec<(&str, Vec<(String, f32)>)> = Vec::new();
+        #[cfg(feature = "parallel")]
+        let sources: Vec<(&str, Vec<(String, f32)>)> = {
+            use std::sync::Mutex;
 
-        // Embedding similarity
-        if let Some(embeddings) = &self.embeddings {
-            if let Some(query_emb) = embeddings.get(query) {
-                let mut scored: Vec<(String, f32)> = embeddings
-                    .iter()
-                    .filter(|(card, _)| *card != query)
-                    .map(|(card, emb)| {
-                        let score = cosine_sim(query_emb, emb);
-                        (card.clone(), score)
-                    })
-                    .collect();
-                scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-                scored.truncate(k * 2); // Get more for fusion
-                sources.push(("embedding", scored));
-            }
+            let results: Mutex<Vec<(&str, Vec<(String, f32)>)>> = Mutex::new(Vec::new());
+            rayon::scope(|s| {
+                s.spawn(|_| {
+                    if let Some(src) = self.score_embedding(query, k) {
+                        results.lock().unwrap().push(src);
+                    }
+                });
+                s.spawn(|_| {
+                    if let Some(src) = self.score_jaccard(query, k) {
+                        results.lock().unwrap().push(src);
+                    }
+                });
+                s.spawn(|_| {
+                    if let Some(src) = self.score_sideboard(query, k) {
+                        results.lock().unwrap().push(src);
+                    }
+                });
+                s.spawn(|_| {
+                    if let Some(src) = self.score_temporal(query, k) {
+                        results.lock().unwrap().push(src);
+                    }
+                });
+                s.spawn(|_| {
+                    if let Some(src) = self.score_gnn(query, k) {
+                        results.lock().unwrap().push(src);
+                    }
+                });
+            });
+
+            // Spawns finish in whatever order the thread pool schedules them; resort into a
+            // fixed signal order so `sources` (and therefore candidate/source attribution
+            // order downstream) is deterministic regardless of scheduling.
+            let mut sources = results.into_inner().unwrap();
+            sources.sort_by_key(|(name, _)| {
+                SIGNAL_ORDER.iter().position(|s| s == name).unwrap_or(SIGNAL_ORDER.len())
+            });
+            sources
+        };
+
+        #[cfg(not(feature = "parallel"))]
+        let sources: Vec<(&str, Vec<(String, f32)>)> = [
+            self.score_embedding(query, k),
+            self.score_jaccard(query, k),
+            self.score_sideboard(query, k),
+            self.score_temporal(query, k),
+            self.score_gnn(query, k),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        // Fuse per-source scores into a final ranking
+        if sources.is_empty() {
+            return Ok(vec![]);
         }
 
-        // Jaccard co-occurrence
-        if let Some(adj) = &self.jaccard_adj {
-            if let Some(neighbors) = adj.get(query) {
-                let mut scored: Vec<(String, f32)> = neighbors
-                    .iter()
-                    .map(|card| {
-                        // Simple: 1.0 if co-occurs, could compute actual Jaccard
-                        (card.clone(), 1.0)
-                    })
-                    .collect();
-                scored.truncate(k * 2);
-                sources.push(("jaccard", scored));
-            }
+        if let Some(ratio) = self.semantic_ratio {
+            return crate::fuse_semantic_ratio(query, &sources, ratio);
         }
 
-        // Sideboard signal
-        if let Some(sb) = &self.sideboard {
-            let mut scored: Vec<(String, f32)> = sb
-                .cooccurrence
-                .get(query)
-                .map(|others| {
-                    others
-                        .iter()
-                        .map(|(card, freq)| (card.clone(), *freq))
-                        .collect()
-                })
-                .unwrap_or_default();
-            scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-            scored.truncate(k);
-            if !scored.is_empty() {
-                sources.push(("sideboard", scored));
+        match &self.fusion_mode {
+            FusionMode::Rrf(rrf_config) => {
+                crate::generate_candidates_fused(query, &sources, *rrf_config)
+            }
+            FusionMode::Weighted { weights } => {
+                crate::generate_candidates_weighted(query, &sources, weights)
             }
         }
+    }
 
-        // Temporal signal
-        if let Some(temp) = &self.temporal {
-            // Get candidates from recent months
-            let mut scored: Vec<(String, f32)> = Vec::new();
-            
-            // Get all unique cards from monthly co-occurrence
-            let mut all_cards = HashSet::new();
-            for month_data in temp.monthly_cooccurrence.values() {
-                if let Some(query_data) = month_data.get(query) {
-                    for card in query_data.keys() {
-                        all_cards.insert(card.clone());
-                    }
-                }
-            }
-            
-            // Score each candidate
-            for card in all_cards {
-                let score = temp.similarity(query, &card);
-                if score > 0.0 {
-                    scored.push((card, score));
-                }
+    /// Embedding similarity: cosine over the whole embeddings map (or an `AnnIndex` lookup)
+    fn score_embedding(&self, query: &str, k: usize) -> Option<(&'static str, Vec<(String, f32)>)> {
+        let embeddings = self.embeddings.as_ref()?;
+        let query_emb = embeddings.get(query)?;
+
+        let mut scored: Vec<(String, f32)> = if let Some(index) = &self.embedding_index {
+            index
+                .query(query_emb, k * 2 + 1) // +1 since the query card itself may come back
+                .into_iter()
+                .filter(|(card, _)| card != query)
+                .collect()
+        } else {
+            #[cfg(feature = "parallel")]
+            {
+                use rayon::prelude::*;
+                embeddings
+                    .par_iter()
+                    .filter(|(card, _)| *card != query)
+                    .map(|(card, emb)| (card.clone(), cosine_sim(query_emb, emb)))
+                    .collect()
             }
-            
-            scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-            scored.truncate(k);
-            if !scored.is_empty() {
-                sources.push(("temporal", scored));
+            #[cfg(not(feature = "parallel"))]
+            {
+                embeddings
+                    .iter()
+                    .filter(|(card, _)| *card != query)
+                    .map(|(card, emb)| (card.clone(), cosine_sim(query_emb, emb)))
+                    .collect()
             }
-        }
+        };
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(k * 2); // Get more for fusion
+        Some(("embedding", scored))
+    }
+
+    /// Jaccard co-occurrence (simple: 1.0 if co-occurs, could compute actual Jaccard)
+    fn score_jaccard(&self, query: &str, k: usize) -> Option<(&'static str, Vec<(String, f32)>)> {
+        let adj = self.jaccard_adj.as_ref()?;
+        let neighbors = adj.get(query)?;
+
+        let mut scored: Vec<(String, f32)> = neighbors.iter().map(|card| (card.clone(), 1.0)).collect();
+        scored.truncate(k * 2);
+        Some(("jaccard", scored))
+    }
+
+    /// Sideboard co-occurrence signal
+    fn score_sideboard(&self, query: &str, k: usize) -> Option<(&'static str, Vec<(String, f32)>)> {
+        let sb = self.sideboard.as_ref()?;
+
+        let mut scored: Vec<(String, f32)> = sb
+            .cooccurrence
+            .get(query)
+            .map(|others| others.iter().map(|(card, freq)| (card.clone(), *freq)).collect())
+            .unwrap_or_default();
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(k);
+
+        (!scored.is_empty()).then_some(("sideboard", scored))
+    }
 
-        // GNN signal (learned graph embeddings)
-        if let Some(gnn) = &self.gnn {
-            let similar = gnn.most_similar(query, k * 2);
-            if !similar.is_empty() {
-                sources.push(("gnn", similar));
+    /// Temporal trend signal (recent months weighted higher)
+    fn score_temporal(&self, query: &str, k: usize) -> Option<(&'static str, Vec<(String, f32)>)> {
+        let temp = self.temporal.as_ref()?;
+
+        // Get all unique cards from monthly co-occurrence
+        let mut all_cards = HashSet::new();
+        for month_data in temp.monthly_cooccurrence.values() {
+            if let Some(query_data) = month_data.get(query) {
+                for card in query_data.keys() {
+                    all_cards.insert(card.clone());
+                }
             }
         }
 
-        // Fuse using rank-fusion
-        if sources.is_empty() {
-            return Ok(vec![]);
-        }
+        #[cfg(feature = "parallel")]
+        let mut scored: Vec<(String, f32)> = {
+            use rayon::prelude::*;
+            all_cards
+                .into_par_iter()
+                .filter_map(|card| {
+                    let score = temp.similarity(query, &card);
+                    (score > 0.0).then_some((card, score))
+                })
+                .collect()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let mut scored: Vec<(String, f32)> = all_cards
+            .into_iter()
+            .filter_map(|card| {
+                let score = temp.similarity(query, &card);
+                (score > 0.0).then_some((card, score))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(k);
+
+        (!scored.is_empty()).then_some(("temporal", scored))
+    }
+
+    /// GNN signal (learned graph embeddings). If `query` has no stored embedding but appears
+    /// in the co-occurrence adjacency, falls back to GraphSAGE-style inductive inference over
+    /// its neighbors (`GNNEmbedder::most_similar_unseen`) so brand-new cards still get scored.
+    fn score_gnn(&self, query: &str, k: usize) -> Option<(&'static str, Vec<(String, f32)>)> {
+        let gnn = self.gnn.as_ref()?;
+
+        let similar = if gnn.get_embedding(query).is_some() {
+            gnn.most_similar(query, k * 2)
+        } else {
+            let neighbors: Vec<String> = self
+                .jaccard_adj
+                .as_ref()
+                .and_then(|adj| adj.get(query))
+                .map(|set| set.iter().cloned().collect())
+                .unwrap_or_default();
+            if neighbors.is_empty() {
+                Vec::new()
+            } else {
+                gnn.most_similar_unseen(query, &neighbors, k * 2)
+            }
+        };
 
-        crate::generate_candidates_fused(query, &sources, self.rrf_config)
+        (!similar.is_empty()).then_some(("gnn", similar))
     }
 }
 